@@ -1,17 +1,209 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::CombinerError;
+
+/// The tiktoken tokenization method used to count tokens in combined files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizationMethod {
+    O200kBase,
+    Cl100kBase,
+    P50kBase,
+    P50kEdit,
+    R50kBase,
+}
+
+impl TokenizationMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenizationMethod::O200kBase => "o200k_base",
+            TokenizationMethod::Cl100kBase => "cl100k_base",
+            TokenizationMethod::P50kBase => "p50k_base",
+            TokenizationMethod::P50kEdit => "p50k_edit",
+            TokenizationMethod::R50kBase => "r50k_base",
+        }
+    }
+}
+
+impl fmt::Display for TokenizationMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for TokenizationMethod {
+    type Err = CombinerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "o200k_base" => Ok(TokenizationMethod::O200kBase),
+            "cl100k_base" => Ok(TokenizationMethod::Cl100kBase),
+            "p50k_base" => Ok(TokenizationMethod::P50kBase),
+            "p50k_edit" => Ok(TokenizationMethod::P50kEdit),
+            "r50k_base" => Ok(TokenizationMethod::R50kBase),
+            other => Err(CombinerError::Config(format!("unknown tokenizer: {other}"))),
+        }
+    }
+}
+
+/// The shape of the combined output file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Concatenate file contents, separated by a `--- File: ... ---` banner.
+    Text,
+    /// Write each file as a tar archive entry, preserving path, size and mtime.
+    Tar,
+}
+
+impl OutputFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Tar => "tar",
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = CombinerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "tar" => Ok(OutputFormat::Tar),
+            other => Err(CombinerError::Config(format!(
+                "unknown output format: {other}"
+            ))),
+        }
+    }
+}
+
+/// How to handle files that aren't valid UTF-8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryMode {
+    /// Drop non-UTF8 files, recording them as skipped.
+    Skip,
+    /// Read non-UTF8 files as raw bytes and embed them as base64.
+    Embed,
+}
+
+impl BinaryMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinaryMode::Skip => "skip",
+            BinaryMode::Embed => "embed",
+        }
+    }
+}
+
+impl fmt::Display for BinaryMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for BinaryMode {
+    type Err = CombinerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(BinaryMode::Skip),
+            "embed" => Ok(BinaryMode::Embed),
+            other => Err(CombinerError::Config(format!(
+                "unknown binary mode: {other}"
+            ))),
+        }
+    }
+}
+
 /// Configuration for the file combiner
 #[derive(Debug, Clone)]
 pub struct CombinerConfig {
     pub directory: String,
     pub output: String,
-    pub tokenizer: String,
+    pub tokenizer: TokenizationMethod,
+    /// Whether files with identical content should be collapsed to a single entry.
+    pub dedup: bool,
+    /// The shape of the combined output file.
+    pub format: OutputFormat,
+    /// How to handle files that aren't valid UTF-8.
+    pub binary: BinaryMode,
+    /// Globs that a file must match to be included. Empty means "no restriction".
+    pub include: Vec<String>,
+    /// Globs that unconditionally exclude a matching file, even if `include` would keep it.
+    pub exclude: Vec<String>,
+    /// Disables `.gitignore`/`.git/info/exclude`/global gitignore filtering.
+    pub no_gitignore: bool,
+    /// An extra gitignore-format file to apply globally, outside the walked tree.
+    pub custom_ignore_file: Option<String>,
+    /// If set, split output across sequentially numbered parts (e.g.
+    /// `combined_output.001.txt`) so that no part exceeds this many tokens.
+    pub max_tokens_per_file: Option<usize>,
 }
 
 impl CombinerConfig {
-    pub fn new(directory: String, output: String, tokenizer: String) -> Self {
+    /// Creates a config with the given required settings and every optional
+    /// knob (dedup, format, binary handling, include/exclude, gitignore) at
+    /// its default. Use the `with_*` methods to override defaults.
+    pub fn new(directory: String, output: String, tokenizer: TokenizationMethod) -> Self {
         Self {
             directory,
             output,
             tokenizer,
+            dedup: false,
+            format: OutputFormat::Text,
+            binary: BinaryMode::Skip,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            no_gitignore: false,
+            custom_ignore_file: None,
+            max_tokens_per_file: None,
         }
     }
+
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_binary(mut self, binary: BinaryMode) -> Self {
+        self.binary = binary;
+        self
+    }
+
+    pub fn with_include(mut self, include: Vec<String>) -> Self {
+        self.include = include;
+        self
+    }
+
+    pub fn with_exclude(mut self, exclude: Vec<String>) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    pub fn with_no_gitignore(mut self, no_gitignore: bool) -> Self {
+        self.no_gitignore = no_gitignore;
+        self
+    }
+
+    pub fn with_custom_ignore_file(mut self, custom_ignore_file: Option<String>) -> Self {
+        self.custom_ignore_file = custom_ignore_file;
+        self
+    }
+
+    pub fn with_max_tokens_per_file(mut self, max_tokens_per_file: Option<usize>) -> Self {
+        self.max_tokens_per_file = max_tokens_per_file;
+        self
+    }
 }
@@ -22,4 +22,3 @@ pub enum CombinerError {
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
-
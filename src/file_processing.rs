@@ -1,20 +1,51 @@
-use crate::config::CombinerConfig;
+use crate::config::{BinaryMode, CombinerConfig, OutputFormat};
 use crate::statistics::Statistics;
 use crate::tokenizer::get_bpe;
 use anyhow::{Context, Result};
-use ignore::Walk;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
 use rayon::prelude::*;
-use std::fs::{File, OpenOptions};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Instant, UNIX_EPOCH};
+
+/// Number of leading bytes hashed when sub-grouping same-size files during dedup.
+const DEDUP_HEAD_BYTES: usize = 4096;
+
+/// The decoded contents of a processed file: readable text, or an opaque
+/// blob that couldn't be decoded as UTF-8 and was embedded as raw bytes.
+#[derive(Debug)]
+enum FileContent {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl FileContent {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            FileContent::Text(text) => text.as_bytes(),
+            FileContent::Binary(bytes) => bytes,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+}
 
 /// Struct to processing results of a file
 #[derive(Debug)]
 struct FileResult {
     path: PathBuf,
-    content: String,
+    content: FileContent,
+    tokens: usize,
 }
 
 /// Combines files from the specified directory into a single output file.
@@ -39,10 +70,25 @@ pub fn combine_files(config: &CombinerConfig) -> Result<Statistics> {
     let bpe = Arc::new(get_bpe(&config.tokenizer));
     let stats = Arc::new(Mutex::new(Statistics::new(
         output_path.display().to_string(),
+        config.tokenizer,
     )));
 
+    let (gitignore_ignored, override_ignored) =
+        count_ignored_files(dir_path, &output_path, config)?;
+    {
+        let mut stats = stats.lock().unwrap();
+        stats.set_ignored_counts(
+            gitignore_ignored.count,
+            gitignore_ignored.bytes,
+            override_ignored.count,
+            override_ignored.bytes,
+        );
+    }
+
+    let overrides = build_overrides(dir_path, config)?;
     // Collect results in a vector
-    let results: Vec<FileResult> = Walk::new(&config.directory)
+    let results: Vec<FileResult> = build_walker(dir_path, config, overrides)?
+        .build()
         .par_bridge()
         .filter_map(|entry| {
             let entry = entry.ok()?;
@@ -53,20 +99,26 @@ pub fn combine_files(config: &CombinerConfig) -> Result<Statistics> {
                 stats.increment_directories_visited();
                 None
             } else if path.is_file() && path != output_path {
-                match process_file(path, &bpe) {
+                match process_file(path, &bpe, config.binary, config.format) {
                     Ok((token_count, content)) => {
+                        let size = content.len() as u64;
                         let mut stats = stats.lock().unwrap();
                         stats.increment_processed_files();
-                        stats.update_token_stats(token_count, path.display().to_string());
+                        stats.update_token_stats(token_count, path.display().to_string(), size);
                         Some(FileResult {
                             path: path.to_path_buf(),
                             content,
+                            tokens: token_count,
                         })
                     }
                     Err(e) => {
-                        eprintln!("Skipped file {}: {}", path.display(), e);
+                        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
                         let mut stats = stats.lock().unwrap();
-                        stats.increment_skipped_files();
+                        stats.increment_skipped_files(
+                            path.display().to_string(),
+                            e.to_string(),
+                            size,
+                        );
                         None
                     }
                 }
@@ -76,54 +128,449 @@ pub fn combine_files(config: &CombinerConfig) -> Result<Statistics> {
         })
         .collect();
 
-    // Write results to the output file
-    let mut output_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&output_path)
-        .context("Failed to create output file")?;
+    let results = if config.dedup {
+        let mut stats = stats.lock().unwrap();
+        deduplicate_results(results, &mut stats)
+    } else {
+        results
+    };
 
-    for result in results {
-        writeln!(output_file, "--- File: {} ---", result.path.display())?;
-        write!(output_file, "{}", result.content)?;
-        writeln!(output_file)?;
+    let (parts, oversized_files) = match config.max_tokens_per_file {
+        Some(budget) => partition_by_token_budget(results, budget),
+        None => (vec![results], Vec::new()),
+    };
+    let single_part = parts.len() <= 1;
+
+    let mut part_stats = Vec::with_capacity(parts.len());
+    for (index, part) in parts.into_iter().enumerate() {
+        let part_tokens: usize = part.iter().map(|result| result.tokens).sum();
+        let part_path = if single_part {
+            output_path.clone()
+        } else {
+            part_output_path(&output_path, index + 1)
+        };
+
+        let part_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&part_path)
+            .context("Failed to create output file")?;
+
+        match config.format {
+            OutputFormat::Text => write_text(part_file, part)?,
+            OutputFormat::Tar => write_tar(part_file, part, dir_path)?,
+        }
+
+        part_stats.push((part_path.display().to_string(), part_tokens));
     }
 
     let mut stats = Arc::try_unwrap(stats)
         .expect("Failed to unwrap Arc")
         .into_inner()
         .expect("Failed to unwrap Mutex");
+    stats.set_output_parts(part_stats, oversized_files);
     stats.processing_time = start_time.elapsed();
 
     Ok(stats)
 }
 
+/// Splits `results` into parts that each stay within `budget` tokens,
+/// preserving order and rolling over to a new part whenever the next file
+/// would push the running total past the budget. A file whose own token
+/// count exceeds `budget` is kept in a part by itself and reported back so
+/// it can be flagged to the user.
+fn partition_by_token_budget(
+    results: Vec<FileResult>,
+    budget: usize,
+) -> (Vec<Vec<FileResult>>, Vec<String>) {
+    let mut parts = Vec::new();
+    let mut current = Vec::new();
+    let mut current_tokens = 0usize;
+    let mut oversized_files = Vec::new();
+
+    for result in results {
+        if result.tokens > budget {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+                current_tokens = 0;
+            }
+            oversized_files.push(result.path.display().to_string());
+            parts.push(vec![result]);
+            continue;
+        }
+
+        if !current.is_empty() && current_tokens + result.tokens > budget {
+            parts.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+
+        current_tokens += result.tokens;
+        current.push(result);
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    (parts, oversized_files)
+}
+
+/// Builds the path for output part `part_number` (1-based), inserting a
+/// zero-padded part number before the file extension, e.g. `out.txt` becomes
+/// `out.001.txt`.
+fn part_output_path(output_path: &Path, part_number: usize) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let file_name = match output_path.extension() {
+        Some(ext) => format!("{stem}.{part_number:03}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{part_number:03}"),
+    };
+
+    output_path.with_file_name(file_name)
+}
+
+/// Builds the include/exclude override matcher from `config`. Plain globs in
+/// `include` act as a whitelist (only matching files are kept, if any are
+/// given); `exclude` globs are added as negated overrides, which always drop
+/// a matching file regardless of `include`.
+fn build_overrides(dir_path: &Path, config: &CombinerConfig) -> Result<Override> {
+    let mut builder = OverrideBuilder::new(dir_path);
+    for pattern in &config.include {
+        builder
+            .add(pattern)
+            .with_context(|| format!("Invalid --include pattern: {pattern}"))?;
+    }
+    for pattern in &config.exclude {
+        builder
+            .add(&format!("!{pattern}"))
+            .with_context(|| format!("Invalid --exclude pattern: {pattern}"))?;
+    }
+    builder
+        .build()
+        .context("Failed to build include/exclude overrides")
+}
+
+/// Applies the gitignore on/off toggle and an optional custom ignore file to
+/// a `WalkBuilder`, shared by the real walk and the accounting walks below.
+fn apply_gitignore_settings<'a>(
+    builder: &'a mut WalkBuilder,
+    config: &CombinerConfig,
+) -> Result<&'a mut WalkBuilder> {
+    let respect_gitignore = !config.no_gitignore;
+    builder
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .ignore(respect_gitignore);
+
+    if let Some(custom_ignore_file) = &config.custom_ignore_file {
+        if let Some(err) = builder.add_ignore(custom_ignore_file) {
+            return Err(anyhow::Error::new(err)
+                .context(format!("Failed to load ignore file: {custom_ignore_file}")));
+        }
+    }
+
+    Ok(builder)
+}
+
+/// Builds the walker used to select files for combining: gitignore semantics
+/// (toggled by `no_gitignore`) plus the `include`/`exclude` overrides.
+fn build_walker(
+    dir_path: &Path,
+    config: &CombinerConfig,
+    overrides: Override,
+) -> Result<WalkBuilder> {
+    let mut builder = WalkBuilder::new(dir_path);
+    builder.overrides(overrides);
+    apply_gitignore_settings(&mut builder, config)?;
+    Ok(builder)
+}
+
+/// Walks `dir_path` with the given builder, returning the set of file paths
+/// it yields (directories and `output_path` excluded).
+fn walked_files(builder: WalkBuilder, output_path: &Path) -> HashSet<PathBuf> {
+    builder
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && path != output_path)
+        .collect()
+}
+
+/// A count of files filtered out at some stage, plus their combined size.
+struct IgnoredStats {
+    count: usize,
+    bytes: u64,
+}
+
+/// Sums the on-disk size of every path in `paths`, treating files that
+/// vanished or became unreadable between the walk and the stat as 0 bytes.
+fn total_size(paths: &HashSet<PathBuf>) -> u64 {
+    paths
+        .iter()
+        .map(|path| {
+            fs::metadata(path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0)
+        })
+        .sum()
+}
+
+/// Determines how many files (and bytes) were filtered out before
+/// processing, and whether gitignore-style rules or `--include`/`--exclude`
+/// overrides were responsible. This walks the tree three times (unfiltered,
+/// gitignore-only, and fully filtered) to isolate each stage's contribution;
+/// the tradeoff is worth it for telling users why a file didn't make it into
+/// the output. The unfiltered walk still skips `.git` directories: they're
+/// never candidates for the real walk either, and descending into one can
+/// mean recursing through the entire git object store just to compute a
+/// stat. Other hidden files are seen by the unfiltered walk but dropped by
+/// the gitignore-only walk's hidden-file filter (which `no_gitignore` does
+/// not turn off), so they are counted in the gitignore stage.
+fn count_ignored_files(
+    dir_path: &Path,
+    output_path: &Path,
+    config: &CombinerConfig,
+) -> Result<(IgnoredStats, IgnoredStats)> {
+    let mut unfiltered = WalkBuilder::new(dir_path);
+    unfiltered.standard_filters(false);
+    unfiltered.filter_entry(|entry| entry.file_name() != ".git");
+    let all_files = walked_files(unfiltered, output_path);
+
+    let mut gitignore_only = WalkBuilder::new(dir_path);
+    apply_gitignore_settings(&mut gitignore_only, config)?;
+    let after_gitignore = walked_files(gitignore_only, output_path);
+
+    let overrides = build_overrides(dir_path, config)?;
+    let fully_filtered = walked_files(build_walker(dir_path, config, overrides)?, output_path);
+
+    let ignored_by_gitignore: HashSet<_> =
+        all_files.difference(&after_gitignore).cloned().collect();
+    let ignored_by_override: HashSet<_> = after_gitignore
+        .difference(&fully_filtered)
+        .cloned()
+        .collect();
+
+    Ok((
+        IgnoredStats {
+            count: ignored_by_gitignore.len(),
+            bytes: total_size(&ignored_by_gitignore),
+        },
+        IgnoredStats {
+            count: ignored_by_override.len(),
+            bytes: total_size(&ignored_by_override),
+        },
+    ))
+}
+
 /// Processes a single file, reading its contents and counting tokens.
 ///
+/// Files that decode as UTF-8 are kept as text. Files that don't are either
+/// dropped (`BinaryMode::Skip`) or read as raw bytes and kept for base64
+/// embedding (`BinaryMode::Embed`). Token counting for the latter depends on
+/// `format`: `OutputFormat::Text` writes the base64 string, so tokens are
+/// counted against that encoded representation; `OutputFormat::Tar` writes
+/// the raw bytes as an archive entry instead, which is never textified or
+/// tokenized, so the file contributes 0 tokens. Either way the count matches
+/// what the writer actually emits.
+///
 /// # Arguments
 ///
 /// * `path` - A reference to the path of the file to process.
 /// * `bpe` - A reference to the CoreBPE tokenizer.
+/// * `binary_mode` - How to handle files that aren't valid UTF-8.
+/// * `format` - The configured output format, which determines how (and
+///   whether) embedded binary content is tokenized.
 ///
 /// # Returns
 ///
 /// Returns a `Result` containing a tuple of the token count and file content if successful.
-fn process_file(path: &Path, bpe: &Arc<tiktoken_rs::CoreBPE>) -> Result<(usize, String)> {
+fn process_file(
+    path: &Path,
+    bpe: &Arc<tiktoken_rs::CoreBPE>,
+    binary_mode: BinaryMode,
+    format: OutputFormat,
+) -> Result<(usize, FileContent)> {
     let mut file = File::open(path).context("Failed to open input file")?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .context("Failed to read input file as UTF-8")?;
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw)
+        .context("Failed to read input file")?;
+
+    match String::from_utf8(raw) {
+        Ok(text) => {
+            let token_count = bpe.encode_with_special_tokens(&text).len();
+            Ok((token_count, FileContent::Text(text)))
+        }
+        Err(e) => match binary_mode {
+            BinaryMode::Skip => Err(anyhow::anyhow!(
+                "Failed to read input file as UTF-8: {}",
+                e.utf8_error()
+            )),
+            BinaryMode::Embed => {
+                let raw = e.into_bytes();
+                let token_count = match format {
+                    OutputFormat::Text => {
+                        let encoded = BASE64.encode(&raw);
+                        bpe.encode_with_special_tokens(&encoded).len()
+                    }
+                    OutputFormat::Tar => 0,
+                };
+                Ok((token_count, FileContent::Binary(raw)))
+            }
+        },
+    }
+}
+
+/// Writes each result as `--- File: path ---` followed by its content; binary
+/// entries are framed with a byte-length marker and base64-encoded.
+fn write_text(mut output_file: File, results: Vec<FileResult>) -> Result<()> {
+    for result in results {
+        writeln!(output_file, "--- File: {} ---", result.path.display())?;
+        match &result.content {
+            FileContent::Text(text) => write!(output_file, "{}", text)?,
+            FileContent::Binary(bytes) => {
+                writeln!(
+                    output_file,
+                    "[embedded binary, {} bytes, base64]",
+                    bytes.len()
+                )?;
+                writeln!(output_file, "{}", BASE64.encode(bytes))?;
+            }
+        }
+        writeln!(output_file)?;
+    }
+    Ok(())
+}
+
+/// Writes each result as a tar archive entry, preserving its path relative to
+/// `dir_path`, its size, and its on-disk modification time.
+fn write_tar(output_file: File, results: Vec<FileResult>, dir_path: &Path) -> Result<()> {
+    let mut builder = tar::Builder::new(output_file);
+
+    for result in results {
+        let entry_path = result.path.strip_prefix(dir_path).unwrap_or(&result.path);
+        let data = result.content.as_bytes();
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(file_mtime(&result.path));
+        header.set_cksum();
+
+        builder
+            .append_data(&mut header, entry_path, data)
+            .with_context(|| format!("Failed to add {} to tar archive", result.path.display()))?;
+    }
+
+    builder.finish().context("Failed to finalize tar archive")?;
+    Ok(())
+}
+
+/// Returns a file's modification time as a unix timestamp, defaulting to 0
+/// (the epoch) if it can't be determined.
+fn file_mtime(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| {
+            modified
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        })
+        .unwrap_or(0)
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Collapses files with identical content down to a single emitted copy.
+///
+/// Candidates are narrowed down in three cheap-to-expensive stages: files are
+/// first grouped by exact byte length (a unique length can't have a
+/// duplicate, so it skips hashing entirely), then by a hash of the leading
+/// `DEDUP_HEAD_BYTES` of content, and finally by a hash of the full content.
+/// A 64-bit hash match is cheap to compute but not collision-proof, so within
+/// each surviving full-hash group files are further clustered by an exact
+/// byte-for-byte comparison before one is dropped as a duplicate; the
+/// lexicographically first path in each byte-identical cluster is kept and
+/// the rest are recorded in `stats` as deduplicated.
+fn deduplicate_results(results: Vec<FileResult>, stats: &mut Statistics) -> Vec<FileResult> {
+    let mut by_size: HashMap<usize, Vec<FileResult>> = HashMap::new();
+    for result in results {
+        by_size
+            .entry(result.content.len())
+            .or_default()
+            .push(result);
+    }
+
+    let mut deduped = Vec::new();
+    for (_, size_group) in by_size {
+        if size_group.len() == 1 {
+            deduped.extend(size_group);
+            continue;
+        }
 
-    let tokens = bpe.encode_with_special_tokens(&contents);
-    let token_count = tokens.len();
+        let mut by_head: HashMap<u64, Vec<FileResult>> = HashMap::new();
+        for result in size_group {
+            let head_len = result.content.len().min(DEDUP_HEAD_BYTES);
+            let head_hash = hash_bytes(&result.content.as_bytes()[..head_len]);
+            by_head.entry(head_hash).or_default().push(result);
+        }
+
+        for (_, head_group) in by_head {
+            if head_group.len() == 1 {
+                deduped.extend(head_group);
+                continue;
+            }
+
+            let mut by_full: HashMap<u64, Vec<FileResult>> = HashMap::new();
+            for result in head_group {
+                let full_hash = hash_bytes(result.content.as_bytes());
+                by_full.entry(full_hash).or_default().push(result);
+            }
+
+            for (_, mut full_group) in by_full {
+                full_group.sort_by(|a, b| a.path.cmp(&b.path));
+
+                // The full-content hash can collide for genuinely different
+                // files, so confirm byte-for-byte equality before treating
+                // anything as a duplicate; a collision just means the group
+                // splits into more than one real cluster.
+                while !full_group.is_empty() {
+                    let canonical = full_group.remove(0);
+                    let (same_content, rest): (Vec<_>, Vec<_>) =
+                        full_group.into_iter().partition(|candidate| {
+                            candidate.content.as_bytes() == canonical.content.as_bytes()
+                        });
+                    full_group = rest;
+
+                    for duplicate in same_content {
+                        stats.record_duplicate(
+                            &duplicate.path.display().to_string(),
+                            duplicate.content.len() as u64,
+                            duplicate.tokens,
+                        );
+                    }
+                    deduped.push(canonical);
+                }
+            }
+        }
+    }
 
-    Ok((token_count, contents))
+    deduped
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::TokenizationMethod;
     use std::fs;
     use std::time::Duration;
     use tempfile::TempDir;
@@ -132,7 +579,6 @@ mod tests {
     fn test_combine_files() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let dir_path = temp_dir.path();
-        let tokenizer = "p50k_base";
 
         // Create test files
         fs::write(dir_path.join("file1.txt"), "Content of file 1")?;
@@ -154,7 +600,7 @@ mod tests {
         let config = CombinerConfig::new(
             dir_path.to_str().unwrap().to_string(),
             output_file.to_str().unwrap().to_string(),
-            tokenizer.to_string(),
+            TokenizationMethod::P50kBase,
         );
         let stats = combine_files(&config)?;
 
@@ -177,4 +623,318 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_combine_files_dedup() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        // Two files share identical content and should collapse to one entry.
+        fs::write(dir_path.join("a.txt"), "duplicate content")?;
+        fs::write(dir_path.join("b.txt"), "duplicate content")?;
+        fs::write(dir_path.join("c.txt"), "unique content")?;
+
+        let output_file = dir_path.join("output.txt");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_dedup(true);
+        let stats = combine_files(&config)?;
+
+        let combined_content = fs::read_to_string(&output_file)?;
+        let occurrences = combined_content.matches("duplicate content").count();
+        assert_eq!(occurrences, 1);
+
+        assert_eq!(stats.files_deduplicated, 1);
+        assert!(stats.bytes_deduplicated > 0);
+
+        // The dropped duplicate must not be double-counted among the
+        // "emitted" stats: only the 2 surviving entries (deduped + unique)
+        // should be reflected in files_processed/bytes_processed/file_stats.
+        assert_eq!(stats.files_processed, 2);
+        assert_eq!(
+            stats.bytes_processed,
+            "duplicate content".len() as u64 + "unique content".len() as u64
+        );
+        assert_eq!(stats.file_stats.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_tar_binary_embed_tokens() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        let invalid_utf8 = vec![0xFF, 0xFE, 0xFD];
+        fs::write(dir_path.join("invalid_utf8.bin"), &invalid_utf8)?;
+
+        let output_file = dir_path.join("output.tar");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_format(OutputFormat::Tar)
+        .with_binary(BinaryMode::Embed);
+        let stats = combine_files(&config)?;
+
+        // The tar entry holds the raw bytes, not a base64 string, so no
+        // tokens should be attributed to this file.
+        assert_eq!(stats.total_tokens, 0);
+
+        let mut archive = tar::Archive::new(File::open(&output_file)?);
+        let mut entries = archive.entries()?;
+        let mut entry = entries.next().unwrap()?;
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        assert_eq!(contents, invalid_utf8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_tar_format() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        fs::write(dir_path.join("file1.txt"), "Content of file 1")?;
+
+        let output_file = dir_path.join("output.tar");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_format(OutputFormat::Tar);
+        combine_files(&config)?;
+
+        let mut archive = tar::Archive::new(File::open(&output_file)?);
+        let mut entries = archive.entries()?;
+        let mut entry = entries.next().unwrap()?;
+        assert_eq!(entry.path()?.to_str().unwrap(), "file1.txt");
+
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents)?;
+        assert_eq!(contents, "Content of file 1");
+        assert!(entries.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_binary_embed() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        let invalid_utf8 = vec![0xFF, 0xFE, 0xFD];
+        fs::write(dir_path.join("invalid_utf8.bin"), &invalid_utf8)?;
+
+        let output_file = dir_path.join("output.txt");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_binary(BinaryMode::Embed);
+        let stats = combine_files(&config)?;
+
+        let combined_content = fs::read_to_string(&output_file)?;
+        assert!(combined_content.contains("[embedded binary, 3 bytes, base64]"));
+        assert!(combined_content.contains(&BASE64.encode(&invalid_utf8)));
+
+        assert_eq!(stats.files_processed, 1);
+        assert_eq!(stats.files_skipped, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_include_exclude() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        fs::write(dir_path.join("keep.rs"), "fn keep() {}")?;
+        fs::write(dir_path.join("also_keep.rs"), "fn also_keep() {}")?;
+        fs::write(dir_path.join("drop.rs"), "fn drop_me() {}")?;
+        fs::write(dir_path.join("notes.txt"), "not a rust file")?;
+
+        let output_file = dir_path.join("output.txt");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_include(vec!["*.rs".to_string()])
+        .with_exclude(vec!["drop.rs".to_string()]);
+        let stats = combine_files(&config)?;
+
+        let combined_content = fs::read_to_string(&output_file)?;
+        assert!(combined_content.contains("fn keep()"));
+        assert!(combined_content.contains("fn also_keep()"));
+        assert!(!combined_content.contains("fn drop_me()"));
+        assert!(!combined_content.contains("not a rust file"));
+
+        assert_eq!(stats.files_processed, 2);
+        assert_eq!(stats.files_ignored_by_override, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_no_gitignore() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        // `.gitignore` rules only apply inside a git repository.
+        fs::create_dir(dir_path.join(".git"))?;
+        fs::write(dir_path.join(".gitignore"), "ignored.txt\n")?;
+        fs::write(dir_path.join("ignored.txt"), "should normally be skipped")?;
+        fs::write(dir_path.join("kept.txt"), "always combined")?;
+
+        let output_file = dir_path.join("output.txt");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_no_gitignore(true);
+        let stats = combine_files(&config)?;
+
+        let combined_content = fs::read_to_string(&output_file)?;
+        assert!(combined_content.contains("should normally be skipped"));
+        assert!(combined_content.contains("always combined"));
+        assert_eq!(stats.files_processed, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_max_tokens_per_file() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        // None of these individually exceed the budget, but all three
+        // together do, so the run must split into more than one part.
+        fs::write(dir_path.join("a.txt"), "one two three")?;
+        fs::write(dir_path.join("b.txt"), "four five six")?;
+        fs::write(dir_path.join("c.txt"), "seven eight nine")?;
+
+        let output_file = dir_path.join("combined_output.txt");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_max_tokens_per_file(Some(5));
+        let stats = combine_files(&config)?;
+
+        assert!(stats.output_parts.len() > 1);
+        assert!(stats.oversized_files.is_empty());
+
+        for (part_path, tokens) in &stats.output_parts {
+            assert!(Path::new(part_path).exists());
+            assert!(*tokens <= 5);
+        }
+
+        let total_tokens: usize = stats.output_parts.iter().map(|(_, tokens)| tokens).sum();
+        assert_eq!(total_tokens, stats.total_tokens);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_max_tokens_per_file_oversized_file() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        fs::write(dir_path.join("huge.txt"), "one two three four five six")?;
+
+        let output_file = dir_path.join("combined_output.txt");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_max_tokens_per_file(Some(2));
+        let stats = combine_files(&config)?;
+
+        assert_eq!(stats.output_parts.len(), 1);
+        assert_eq!(stats.oversized_files.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_byte_accounting() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        fs::write(dir_path.join("kept.txt"), "kept content")?;
+        fs::write(dir_path.join("excluded.txt"), "excluded by override")?;
+        let invalid_utf8 = vec![0xFF, 0xFE, 0xFD];
+        fs::write(dir_path.join("invalid_utf8.bin"), &invalid_utf8)?;
+
+        let output_file = dir_path.join("output.txt");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_exclude(vec!["excluded.txt".to_string()]);
+        let stats = combine_files(&config)?;
+
+        assert_eq!(stats.bytes_processed, "kept content".len() as u64);
+        assert_eq!(stats.bytes_skipped, invalid_utf8.len() as u64);
+        assert_eq!(
+            stats.bytes_ignored_by_override,
+            "excluded by override".len() as u64
+        );
+        assert_eq!(stats.bytes_ignored, stats.bytes_ignored_by_override);
+        assert_eq!(
+            stats.bytes_considered(),
+            stats.bytes_processed + stats.bytes_skipped + stats.bytes_ignored
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combine_files_byte_accounting_with_dedup() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dir_path = temp_dir.path();
+
+        fs::write(dir_path.join("a.txt"), "duplicate content")?;
+        fs::write(dir_path.join("b.txt"), "duplicate content")?;
+        fs::write(dir_path.join("c.txt"), "unique content")?;
+
+        let output_file = dir_path.join("output.txt");
+        let config = CombinerConfig::new(
+            dir_path.to_str().unwrap().to_string(),
+            output_file.to_str().unwrap().to_string(),
+            TokenizationMethod::P50kBase,
+        )
+        .with_dedup(true);
+        let stats = combine_files(&config)?;
+
+        // The deduplicated copy was genuinely read off disk during the walk,
+        // so it must still count toward "considered" even though it's no
+        // longer counted as "processed" (emitted).
+        assert_eq!(stats.bytes_deduplicated, "duplicate content".len() as u64);
+        assert_eq!(
+            stats.bytes_considered(),
+            stats.bytes_processed
+                + stats.bytes_skipped
+                + stats.bytes_ignored
+                + stats.bytes_deduplicated
+        );
+        assert_eq!(
+            stats.bytes_considered(),
+            "duplicate content".len() as u64 * 2 + "unique content".len() as u64
+        );
+
+        Ok(())
+    }
 }
@@ -1,4 +1,7 @@
-use combiner::{combine_files, print_statistics, CombinerConfig};
+use combiner::{
+    combine_files, print_statistics, BinaryMode, CombinerConfig, OutputFormat, TokenizationMethod,
+};
+use std::str::FromStr;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -20,11 +23,59 @@ struct Opt {
         possible_values = &["o200k_base", "cl100k_base", "p50k_base", "p50k_edit", "r50k_base"]
     )]
     tokenizer: String,
+
+    /// Skip emitting a file if its content duplicates one already combined
+    #[structopt(long)]
+    dedup: bool,
+
+    /// Output format: a delimited text blob, or a tar archive of the combined files
+    #[structopt(
+        long,
+        default_value = "text",
+        possible_values = &["text", "tar"]
+    )]
+    format: String,
+
+    /// How to handle files that aren't valid UTF-8: drop them, or embed them as base64
+    #[structopt(long, default_value = "skip", possible_values = &["skip", "embed"])]
+    binary: String,
+
+    /// Only include files matching this glob (repeatable)
+    #[structopt(long)]
+    include: Vec<String>,
+
+    /// Exclude files matching this glob, even if --include would keep them (repeatable)
+    #[structopt(long)]
+    exclude: Vec<String>,
+
+    /// Ignore .gitignore, .git/info/exclude, and global gitignore rules
+    #[structopt(long)]
+    no_gitignore: bool,
+
+    /// Path to an extra gitignore-format file to apply on top of the above
+    #[structopt(long)]
+    ignore_file: Option<String>,
+
+    /// Split output into sequentially numbered parts (e.g. combined_output.001.txt)
+    /// that each stay under this many tokens
+    #[structopt(long)]
+    max_tokens_per_file: Option<usize>,
 }
 
 fn main() -> anyhow::Result<()> {
     let opt = Opt::from_args();
-    let config = CombinerConfig::new(opt.directory, opt.output, opt.tokenizer);
+    let tokenizer = TokenizationMethod::from_str(&opt.tokenizer)?;
+    let format = OutputFormat::from_str(&opt.format)?;
+    let binary = BinaryMode::from_str(&opt.binary)?;
+    let config = CombinerConfig::new(opt.directory, opt.output, tokenizer)
+        .with_dedup(opt.dedup)
+        .with_format(format)
+        .with_binary(binary)
+        .with_include(opt.include)
+        .with_exclude(opt.exclude)
+        .with_no_gitignore(opt.no_gitignore)
+        .with_custom_ignore_file(opt.ignore_file)
+        .with_max_tokens_per_file(opt.max_tokens_per_file);
     let stats = combine_files(&config)?;
     print_statistics(&stats);
     Ok(())
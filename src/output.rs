@@ -6,6 +6,7 @@ use crate::config::TokenizationMethod;
 
 pub const TOP_FILES_TO_SHOW: usize = 10;
 
+#[allow(clippy::too_many_arguments)]
 pub fn print_table(
     files_processed: usize,
     total_tokens: usize,
@@ -15,15 +16,21 @@ pub fn print_table(
     tokenization_method: &TokenizationMethod,
     files_failed: usize,
     files_ignored: usize,
+    directories_visited: usize,
+    max_tokens: usize,
+    max_tokens_file: &str,
 ) {
     let mut table = Table::new();
     table.add_row(row!["Statistic", "Value"]);
+    table.add_row(row!["Output File", output_file.to_string_lossy()]);
     table.add_row(row!["Files Processed", files_processed]);
     table.add_row(row!["Files Failed", files_failed]);
     table.add_row(row!["Files Ignored", files_ignored]);
+    table.add_row(row!["Directories Visited", directories_visited]);
     table.add_row(row!["Total Tokens", total_tokens]);
+    table.add_row(row!["Max Tokens", max_tokens]);
+    table.add_row(row!["File with Max Tokens", max_tokens_file]);
     table.add_row(row!["Tokenization Method", tokenization_method.to_string()]);
-    table.add_row(row!["Output File", output_file.to_string_lossy()]);
     table.add_row(row!["Processing Time", format!("{:.2?}", processing_time)]);
 
     let total_size: u64 = file_stats.iter().map(|(_, _, size)| size).sum();
@@ -58,7 +65,7 @@ pub fn print_table(
 
     // Sort file_stats by token count (descending) and take top N
     let mut sorted_stats = file_stats.to_vec();
-    sorted_stats.sort_by(|a, b| b.1.cmp(&a.1));
+    sorted_stats.sort_by_key(|(_, tokens, _)| std::cmp::Reverse(*tokens));
     for (file, tokens, size) in sorted_stats.iter().take(TOP_FILES_TO_SHOW) {
         details_table.add_row(row![file, tokens, size]);
     }
@@ -80,3 +87,52 @@ pub fn print_skipped_files(skipped_files: &[(String, String)]) {
     skipped_table.printstd();
 }
 
+/// Prints the output parts produced when `--max-tokens-per-file` splits the
+/// run across multiple files. Silent when there's only a single part, since
+/// that's the common (unsplit) case.
+pub fn print_output_parts(output_parts: &[(String, usize)], oversized_files: &[String]) {
+    if output_parts.len() <= 1 {
+        return;
+    }
+
+    println!("\nOutput Parts ({}):", output_parts.len());
+    let mut parts_table = Table::new();
+    parts_table.add_row(row!["Part", "Tokens"]);
+    for (part, tokens) in output_parts {
+        parts_table.add_row(row![part, tokens]);
+    }
+    parts_table.printstd();
+
+    if !oversized_files.is_empty() {
+        println!("\nFiles Exceeding --max-tokens-per-file (given their own part):");
+        let mut oversized_table = Table::new();
+        oversized_table.add_row(row!["File"]);
+        for file in oversized_files {
+            oversized_table.add_row(row![file]);
+        }
+        oversized_table.printstd();
+    }
+}
+
+/// Prints a byte-level "in vs. out" breakdown of the run: how much data was
+/// considered overall, and how it split between emitted, skipped, ignored,
+/// and (if dedup ran) redundant bytes.
+pub fn print_byte_accounting(
+    bytes_considered: u64,
+    bytes_processed: u64,
+    bytes_skipped: u64,
+    bytes_ignored: u64,
+    bytes_deduplicated: u64,
+) {
+    println!("\nByte Accounting:");
+    let mut table = Table::new();
+    table.add_row(row!["Category", "Bytes"]);
+    table.add_row(row!["Considered", bytes_considered]);
+    table.add_row(row!["Processed (emitted)", bytes_processed]);
+    table.add_row(row!["Skipped (failed to process)", bytes_skipped]);
+    table.add_row(row!["Ignored (gitignore/overrides)", bytes_ignored]);
+    if bytes_deduplicated > 0 {
+        table.add_row(row!["Redundant (deduplicated)", bytes_deduplicated]);
+    }
+    table.printstd();
+}
@@ -1,6 +1,9 @@
-use prettytable::{row, Table};
+use std::path::Path;
 use std::time::Duration;
 
+use crate::config::TokenizationMethod;
+use crate::output;
+
 /// Represents the statistics collected during the file combining process.
 #[derive(Debug)]
 pub struct Statistics {
@@ -12,11 +15,45 @@ pub struct Statistics {
     pub max_tokens_file: String,
     pub processing_time: Duration,
     pub output_file: String,
+    pub tokenization_method: TokenizationMethod,
+    /// Per-file `(path, tokens, size in bytes)` used for the details table.
+    pub file_stats: Vec<(String, usize, u64)>,
+    /// Files that could not be processed, paired with the failure reason.
+    pub skipped_files: Vec<(String, String)>,
+    /// Files excluded by gitignore/override filtering rather than a processing failure.
+    pub files_ignored: usize,
+    /// Of `files_ignored`, how many were excluded by `.gitignore`-style rules
+    /// or, since that stage also applies the walker's hidden-file filter, by
+    /// being hidden.
+    pub files_ignored_by_gitignore: usize,
+    /// Of `files_ignored`, how many were excluded by `--include`/`--exclude` overrides.
+    pub files_ignored_by_override: usize,
+    pub files_deduplicated: usize,
+    pub bytes_deduplicated: u64,
+    pub tokens_deduplicated: usize,
+    /// Total bytes of files that were successfully read and emitted.
+    pub bytes_processed: u64,
+    /// Total bytes of files that failed to process (see `skipped_files`).
+    pub bytes_skipped: u64,
+    /// Of the bytes filtered out before processing, how many were excluded
+    /// by `.gitignore`-style rules or by the hidden-file filter.
+    pub bytes_ignored_by_gitignore: u64,
+    /// Of the bytes filtered out before processing, how many were excluded
+    /// by `--include`/`--exclude` overrides.
+    pub bytes_ignored_by_override: u64,
+    /// `bytes_ignored_by_gitignore + bytes_ignored_by_override`.
+    pub bytes_ignored: u64,
+    /// Per-part `(path, tokens)` when output was split by `max_tokens_per_file`.
+    /// A single part (the whole run) when splitting wasn't requested.
+    pub output_parts: Vec<(String, usize)>,
+    /// Files that on their own exceed the token budget and were placed in
+    /// their own part regardless.
+    pub oversized_files: Vec<String>,
 }
 
 impl Statistics {
     /// Creates a new Statistics instance with default values.
-    pub fn new(output_file: String) -> Self {
+    pub fn new(output_file: String, tokenization_method: TokenizationMethod) -> Self {
         Self {
             files_processed: 0,
             files_skipped: 0,
@@ -26,6 +63,22 @@ impl Statistics {
             max_tokens_file: String::new(),
             processing_time: Duration::default(),
             output_file,
+            tokenization_method,
+            file_stats: Vec::new(),
+            skipped_files: Vec::new(),
+            files_ignored: 0,
+            files_ignored_by_gitignore: 0,
+            files_ignored_by_override: 0,
+            files_deduplicated: 0,
+            bytes_deduplicated: 0,
+            tokens_deduplicated: 0,
+            bytes_processed: 0,
+            bytes_skipped: 0,
+            bytes_ignored_by_gitignore: 0,
+            bytes_ignored_by_override: 0,
+            bytes_ignored: 0,
+            output_parts: Vec::new(),
+            oversized_files: Vec::new(),
         }
     }
 
@@ -35,17 +88,21 @@ impl Statistics {
     }
 
     /// Updates the statistics with information from a processed file.
-    pub fn update_token_stats(&mut self, tokens: usize, file_path: String) {
+    pub fn update_token_stats(&mut self, tokens: usize, file_path: String, size: u64) {
         self.total_tokens += tokens;
+        self.bytes_processed += size;
         if tokens > self.max_tokens {
             self.max_tokens = tokens;
-            self.max_tokens_file = file_path;
+            self.max_tokens_file = file_path.clone();
         }
+        self.file_stats.push((file_path, tokens, size));
     }
 
-    /// Increments the count of skipped files.
-    pub fn increment_skipped_files(&mut self) {
+    /// Records a file that failed to process, along with why and its size.
+    pub fn increment_skipped_files(&mut self, file_path: String, reason: String, size: u64) {
         self.files_skipped += 1;
+        self.bytes_skipped += size;
+        self.skipped_files.push((file_path, reason));
     }
 
     /// Increments the count of visited directories.
@@ -53,26 +110,104 @@ impl Statistics {
         self.directories_visited += 1;
     }
 
+    /// Records how many files (and bytes) were filtered out before
+    /// processing, broken down by whether gitignore-style rules or
+    /// `--include`/`--exclude` overrides were responsible.
+    pub fn set_ignored_counts(
+        &mut self,
+        by_gitignore: usize,
+        bytes_by_gitignore: u64,
+        by_override: usize,
+        bytes_by_override: u64,
+    ) {
+        self.files_ignored_by_gitignore = by_gitignore;
+        self.files_ignored_by_override = by_override;
+        self.files_ignored = by_gitignore + by_override;
+        self.bytes_ignored_by_gitignore = bytes_by_gitignore;
+        self.bytes_ignored_by_override = bytes_by_override;
+        self.bytes_ignored = bytes_by_gitignore + bytes_by_override;
+    }
+
+    /// Total bytes of files the walk encountered, whether they ended up
+    /// processed, skipped, filtered out entirely, or dropped as a duplicate.
+    /// `bytes_processed` only counts what was actually emitted, so
+    /// `bytes_deduplicated` (bytes read but collapsed away by dedup) has to
+    /// be added back in separately to get the true "considered" total.
+    pub fn bytes_considered(&self) -> u64 {
+        self.bytes_processed + self.bytes_skipped + self.bytes_ignored + self.bytes_deduplicated
+    }
+
+    /// Records a file whose content duplicated an already-emitted file.
+    ///
+    /// The duplicate was counted as processed during the initial walk (before
+    /// dedup ran), so its contribution to the "emitted" stats is backed out
+    /// here: `files_processed`, `bytes_processed`, and `file_stats` must only
+    /// reflect what actually ends up in the output.
+    pub fn record_duplicate(&mut self, file_path: &str, bytes: u64, tokens: usize) {
+        self.files_deduplicated += 1;
+        self.bytes_deduplicated += bytes;
+        self.tokens_deduplicated += tokens;
+        self.total_tokens = self.total_tokens.saturating_sub(tokens);
+
+        self.files_processed = self.files_processed.saturating_sub(1);
+        self.bytes_processed = self.bytes_processed.saturating_sub(bytes);
+        self.file_stats.retain(|(path, _, _)| path != file_path);
+
+        if self.max_tokens_file == file_path {
+            self.recompute_max_tokens();
+        }
+    }
+
+    /// Recomputes `max_tokens`/`max_tokens_file` from `file_stats`. Used
+    /// after removing a duplicate that was holding the current max.
+    fn recompute_max_tokens(&mut self) {
+        match self.file_stats.iter().max_by_key(|(_, tokens, _)| *tokens) {
+            Some((path, tokens, _)) => {
+                self.max_tokens = *tokens;
+                self.max_tokens_file = path.clone();
+            }
+            None => {
+                self.max_tokens = 0;
+                self.max_tokens_file = String::new();
+            }
+        }
+    }
+
     /// Sets the processing time.
     pub fn set_processing_time(&mut self, duration: Duration) {
         self.processing_time = duration;
     }
 
+    /// Records the parts the output was split into, and which (if any) files
+    /// individually exceeded the token budget and were given their own part.
+    pub fn set_output_parts(&mut self, parts: Vec<(String, usize)>, oversized_files: Vec<String>) {
+        self.output_parts = parts;
+        self.oversized_files = oversized_files;
+    }
+
     /// Prints the statistics in a formatted table.
     pub fn print(&self) {
-        let mut table = Table::new();
-        table.add_row(row!["Statistic", "Value"]);
-        table.add_row(row!["Output File", &self.output_file]);
-        table.add_row(row!["Files Processed", self.files_processed]);
-        table.add_row(row!["Files Skipped", self.files_skipped]);
-        table.add_row(row!["Directories Visited", self.directories_visited]);
-        table.add_row(row!["Total Tokens", self.total_tokens]);
-        table.add_row(row!["Max Tokens", self.max_tokens]);
-        table.add_row(row!["File with Max Tokens", &self.max_tokens_file]);
-        table.add_row(row![
-            "Processing Time",
-            format!("{:.2?}", self.processing_time)
-        ]);
-        table.printstd();
+        output::print_table(
+            self.files_processed,
+            self.total_tokens,
+            Path::new(&self.output_file),
+            &self.file_stats,
+            self.processing_time,
+            &self.tokenization_method,
+            self.files_skipped,
+            self.files_ignored,
+            self.directories_visited,
+            self.max_tokens,
+            &self.max_tokens_file,
+        );
+        output::print_skipped_files(&self.skipped_files);
+        output::print_output_parts(&self.output_parts, &self.oversized_files);
+        output::print_byte_accounting(
+            self.bytes_considered(),
+            self.bytes_processed,
+            self.bytes_skipped,
+            self.bytes_ignored,
+            self.bytes_deduplicated,
+        );
     }
 }